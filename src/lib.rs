@@ -1,4 +1,3 @@
-use std::str::FromStr;
 use thiserror::Error;
 
 // Custom errors for Bitcoin operations
@@ -28,6 +27,237 @@ impl<T> Point<T> {
     }
 }
 
+// secp256k1 base point G, used by `Point::make_even` to walk a point to
+// even Y by repeated addition.
+pub const SECP256K1_GENERATOR: Point<[u8; 32]> = Point {
+    x: [
+        0x79, 0xBE, 0x66, 0x7E, 0xF9, 0xDC, 0xBB, 0xAC, 0x55, 0xA0, 0x62, 0x95, 0xCE, 0x87, 0x0B,
+        0x07, 0x02, 0x9B, 0xFC, 0xDB, 0x2D, 0xCE, 0x28, 0xD9, 0x59, 0xF2, 0x81, 0x5B, 0x16, 0xF8,
+        0x17, 0x98,
+    ],
+    y: [
+        0x48, 0x3A, 0xDA, 0x77, 0x26, 0xA3, 0xC4, 0x65, 0x5D, 0xA4, 0xFB, 0xFC, 0x0E, 0x11, 0x08,
+        0xA8, 0xFD, 0x17, 0xB4, 0x48, 0xA6, 0x85, 0x54, 0x19, 0x9C, 0x47, 0xD0, 0x8F, 0xFB, 0x10,
+        0xD4, 0xB8,
+    ],
+};
+
+impl Point<[u8; 32]> {
+    /// Returns the BIP-340 x-only public key: the big-endian x-coordinate.
+    ///
+    /// Must only be called on a finite point with an even Y coordinate —
+    /// call [`Point::make_even`] first if the point's Y parity is unknown.
+    pub fn x_only(&self) -> Result<[u8; 32], BitcoinError> {
+        if self.is_infinity() || !self.has_even_y() {
+            return Err(BitcoinError::InvalidScript);
+        }
+        Ok(self.x)
+    }
+
+    /// Repeatedly adds the secp256k1 generator to `self` until the Y
+    /// coordinate is even, returning the adjusted point together with the
+    /// number of additions performed. This is the even-Y normalization
+    /// BIP-340 Taproot output keys require.
+    pub fn make_even(&self) -> (Point<[u8; 32]>, u64) {
+        let mut point = self.clone();
+        let mut additions = 0u64;
+        while !point.has_even_y() {
+            point = point.add(&SECP256K1_GENERATOR);
+            additions += 1;
+        }
+        (point, additions)
+    }
+
+    fn is_infinity(&self) -> bool {
+        self.x == [0u8; 32] && self.y == [0u8; 32]
+    }
+
+    fn has_even_y(&self) -> bool {
+        self.y[31] & 1 == 0
+    }
+
+    fn add(&self, other: &Point<[u8; 32]>) -> Point<[u8; 32]> {
+        if self.is_infinity() {
+            return other.clone();
+        }
+        if other.is_infinity() {
+            return self.clone();
+        }
+
+        let ax = limbs_from_be_bytes(&self.x);
+        let ay = limbs_from_be_bytes(&self.y);
+        let bx = limbs_from_be_bytes(&other.x);
+        let by = limbs_from_be_bytes(&other.y);
+
+        let lambda = if ax == bx {
+            if fe_add(&ay, &by) == [0u64; 4] {
+                return Point::new([0u8; 32], [0u8; 32]);
+            }
+            let three_x_sq = fe_mul(&[3, 0, 0, 0], &fe_mul(&ax, &ax));
+            let two_y = fe_mul(&[2, 0, 0, 0], &ay);
+            fe_mul(&three_x_sq, &fe_inv(&two_y))
+        } else {
+            fe_mul(&fe_sub(&by, &ay), &fe_inv(&fe_sub(&bx, &ax)))
+        };
+
+        let x3 = fe_sub(&fe_sub(&fe_mul(&lambda, &lambda), &ax), &bx);
+        let y3 = fe_sub(&fe_mul(&lambda, &fe_sub(&ax, &x3)), &ay);
+
+        Point::new(limbs_to_be_bytes(&x3), limbs_to_be_bytes(&y3))
+    }
+}
+
+// --- Minimal secp256k1 field arithmetic backing `Point<[u8; 32]>` ---
+
+type Limbs = [u64; 4]; // little-endian 64-bit limbs, value < SECP256K1_P
+
+const SECP256K1_P: Limbs = [
+    0xFFFFFFFEFFFFFC2F,
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+];
+
+fn limbs_from_be_bytes(bytes: &[u8; 32]) -> Limbs {
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let start = 24 - i * 8;
+        *limb = u64::from_be_bytes(bytes[start..start + 8].try_into().unwrap());
+    }
+    limbs
+}
+
+fn limbs_to_be_bytes(limbs: &Limbs) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (i, limb) in limbs.iter().enumerate() {
+        let start = 24 - i * 8;
+        bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+fn limbs_cmp(a: &Limbs, b: &Limbs) -> std::cmp::Ordering {
+    for i in (0..4).rev() {
+        match a[i].cmp(&b[i]) {
+            std::cmp::Ordering::Equal => continue,
+            ord => return ord,
+        }
+    }
+    std::cmp::Ordering::Equal
+}
+
+fn limbs_add_raw(a: &Limbs, b: &Limbs) -> (Limbs, bool) {
+    let mut result = [0u64; 4];
+    let mut carry = 0u128;
+    for i in 0..4 {
+        let sum = a[i] as u128 + b[i] as u128 + carry;
+        result[i] = sum as u64;
+        carry = sum >> 64;
+    }
+    (result, carry != 0)
+}
+
+fn limbs_sub(a: &Limbs, b: &Limbs) -> Limbs {
+    let mut result = [0u64; 4];
+    let mut borrow = 0i128;
+    for i in 0..4 {
+        let diff = a[i] as i128 - b[i] as i128 - borrow;
+        if diff < 0 {
+            result[i] = (diff + (1i128 << 64)) as u64;
+            borrow = 1;
+        } else {
+            result[i] = diff as u64;
+            borrow = 0;
+        }
+    }
+    result
+}
+
+fn fe_add(a: &Limbs, b: &Limbs) -> Limbs {
+    let (sum, overflow) = limbs_add_raw(a, b);
+    if overflow || limbs_cmp(&sum, &SECP256K1_P) != std::cmp::Ordering::Less {
+        limbs_sub(&sum, &SECP256K1_P)
+    } else {
+        sum
+    }
+}
+
+fn fe_sub(a: &Limbs, b: &Limbs) -> Limbs {
+    if limbs_cmp(a, b) == std::cmp::Ordering::Less {
+        let (sum, _) = limbs_add_raw(a, &SECP256K1_P);
+        limbs_sub(&sum, b)
+    } else {
+        limbs_sub(a, b)
+    }
+}
+
+fn limbs_mul_wide(a: &Limbs, b: &Limbs) -> [u64; 8] {
+    let mut result = [0u64; 8];
+    for (i, &a_limb) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &b_limb) in b.iter().enumerate() {
+            let idx = i + j;
+            let product = a_limb as u128 * b_limb as u128 + result[idx] as u128 + carry;
+            result[idx] = product as u64;
+            carry = product >> 64;
+        }
+        result[i + 4] = (result[i + 4] as u128 + carry) as u64;
+    }
+    result
+}
+
+// Reduces a 512-bit product mod SECP256K1_P via bit-serial binary long
+// division, folding each shifted-out top bit back in as 2^256 mod P.
+fn wide_mod_p(wide: &[u64; 8]) -> Limbs {
+    const TWO_POW_256_MOD_P: Limbs = [977 + (1u64 << 32), 0, 0, 0];
+
+    let mut remainder: Limbs = [0u64; 4];
+    for word_idx in (0..8).rev() {
+        for bit in (0..64).rev() {
+            let bit_val = (wide[word_idx] >> bit) & 1;
+            let mut carry = bit_val;
+            for limb in remainder.iter_mut() {
+                let new_carry = *limb >> 63;
+                *limb = (*limb << 1) | carry;
+                carry = new_carry;
+            }
+            if carry == 1 {
+                let (sum, _) = limbs_add_raw(&remainder, &TWO_POW_256_MOD_P);
+                remainder = sum;
+            }
+            while limbs_cmp(&remainder, &SECP256K1_P) != std::cmp::Ordering::Less {
+                remainder = limbs_sub(&remainder, &SECP256K1_P);
+            }
+        }
+    }
+    remainder
+}
+
+fn fe_mul(a: &Limbs, b: &Limbs) -> Limbs {
+    wide_mod_p(&limbs_mul_wide(a, b))
+}
+
+fn fe_pow(base: &Limbs, exponent: &Limbs) -> Limbs {
+    let mut result: Limbs = [1, 0, 0, 0];
+    let mut base = *base;
+    for word in exponent {
+        for bit in 0..64 {
+            if (word >> bit) & 1 == 1 {
+                result = fe_mul(&result, &base);
+            }
+            base = fe_mul(&base, &base);
+        }
+    }
+    result
+}
+
+// Modular inverse via Fermat's little theorem: a^(p-2) = a^-1 mod p for
+// prime p.
+fn fe_inv(a: &Limbs) -> Limbs {
+    let p_minus_2 = limbs_sub(&SECP256K1_P, &[2, 0, 0, 0]);
+    fe_pow(a, &p_minus_2)
+}
+
 // Custom serialization for Bitcoin transaction
 pub trait BitcoinSerialize {
     fn serialize(&self) -> Vec<u8>;
@@ -110,14 +340,48 @@ impl LegacyTransactionBuilder {
         }
         // TODO: Build and return the final LegacyTransaction
     }
+
+    /// Appends a change output equal to `sum(input_values) - amount - fee`,
+    /// omitting it entirely when the computed change is below the dust
+    /// threshold. Fails with `BitcoinError::InvalidAmount` when the inputs
+    /// cannot cover `amount + fee`.
+    pub fn with_fee(
+        self,
+        input_values: &[u64],
+        amount: u64,
+        fee: u64,
+        change_script_pubkey: Vec<u8>,
+    ) -> Result<Self, BitcoinError> {
+        let total_input: u64 = input_values.iter().sum();
+        let required = amount.checked_add(fee).ok_or(BitcoinError::InvalidAmount)?;
+        let change = total_input
+            .checked_sub(required)
+            .ok_or(BitcoinError::InvalidAmount)?;
+
+        if change < DUST_THRESHOLD {
+            return Ok(self);
+        }
+
+        Ok(self.add_output(TxOutput {
+            value: change,
+            script_pubkey: change_script_pubkey,
+        }))
+    }
 }
 
+// Below this, a change output isn't worth the fee it would cost to spend.
+const DUST_THRESHOLD: u64 = 546;
+
+// Applied to `send` when the caller does not specify a fee explicitly.
+const DEFAULT_FEE: u64 = 1000;
+
 // Transaction components
 #[derive(Debug, Clone)]
 pub struct TxInput {
     pub previous_output: OutPoint,
     pub script_sig: Vec<u8>,
     pub sequence: u32,
+    pub witness: Vec<Vec<u8>>,
 }
 
 #[derive(Debug, Clone)]
@@ -132,8 +396,417 @@ pub struct OutPoint {
     pub vout: u32,
 }
 
+// The Bitcoin network an address (or transaction) was encoded for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+// The script type a decoded address resolves to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressKind {
+    P2pkh,
+    P2sh,
+    // Every witness program other than a 32-byte v1 one, so v1 programs of
+    // other lengths and versions 2-16 land here as well as version 0.
+    SegwitV0,
+    Taproot,
+}
+
+// A decoded, network-checked Bitcoin address together with the
+// `script_pubkey` it spends to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    pub network: Network,
+    pub kind: AddressKind,
+    pub script_pubkey: Vec<u8>,
+}
+
+impl Address {
+    /// Parses a Base58Check (P2PKH/P2SH) or Bech32/Bech32m (SegWit) address
+    /// and checks that it was encoded for `expected_network`.
+    pub fn parse(s: &str, expected_network: Network) -> Result<Self, BitcoinError> {
+        let address = match Self::parse_base58check(s, expected_network) {
+            Ok(address) => address,
+            Err(_) => Self::parse_bech32(s)?,
+        };
+
+        if address.network != expected_network {
+            return Err(BitcoinError::ParseError(format!(
+                "address is for {:?} but expected {:?}",
+                address.network, expected_network
+            )));
+        }
+
+        Ok(address)
+    }
+
+    fn parse_base58check(s: &str, expected_network: Network) -> Result<Self, BitcoinError> {
+        let data = base58_decode(s)?;
+        if data.len() != 25 {
+            return Err(BitcoinError::ParseError(
+                "invalid Base58Check payload length".to_string(),
+            ));
+        }
+
+        let (payload, checksum) = data.split_at(21);
+        if double_sha256(payload)[..4] != *checksum {
+            return Err(BitcoinError::ParseError(
+                "invalid Base58Check checksum".to_string(),
+            ));
+        }
+
+        let version = payload[0];
+        let hash = &payload[1..];
+
+        // Base58Check regtest addresses reuse the testnet version bytes, so
+        // a 0x6f/0xc4 address resolves to whichever of the two was expected.
+        let testnet_like = if expected_network == Network::Regtest {
+            Network::Regtest
+        } else {
+            Network::Testnet
+        };
+
+        let (network, kind, script_pubkey) = match version {
+            0x00 => (Network::Mainnet, AddressKind::P2pkh, p2pkh_script(hash)),
+            0x05 => (Network::Mainnet, AddressKind::P2sh, p2sh_script(hash)),
+            0x6f => (testnet_like, AddressKind::P2pkh, p2pkh_script(hash)),
+            0xc4 => (testnet_like, AddressKind::P2sh, p2sh_script(hash)),
+            other => {
+                return Err(BitcoinError::ParseError(format!(
+                    "unknown Base58Check version byte: {other:#x}"
+                )))
+            }
+        };
+
+        Ok(Address {
+            network,
+            kind,
+            script_pubkey,
+        })
+    }
+
+    fn parse_bech32(s: &str) -> Result<Self, BitcoinError> {
+        let (hrp, data, variant) = bech32_decode(s)?;
+        let network = match hrp.as_str() {
+            "bc" => Network::Mainnet,
+            "tb" => Network::Testnet,
+            "bcrt" => Network::Regtest,
+            other => {
+                return Err(BitcoinError::ParseError(format!(
+                    "unknown bech32 human-readable part: {other}"
+                )))
+            }
+        };
+
+        let (&witness_version, program_5bit) = data
+            .split_first()
+            .ok_or_else(|| BitcoinError::ParseError("empty bech32 payload".to_string()))?;
+        if witness_version > 16 {
+            return Err(BitcoinError::ParseError(
+                "invalid witness version".to_string(),
+            ));
+        }
+        let program = convert_bits(program_5bit, 5, 8, false)?;
+
+        match (witness_version, variant) {
+            (0, Bech32Variant::Bech32) => {
+                if program.len() != 20 && program.len() != 32 {
+                    return Err(BitcoinError::ParseError(
+                        "invalid v0 witness program length".to_string(),
+                    ));
+                }
+            }
+            (1..=16, Bech32Variant::Bech32m) => {
+                if !(2..=40).contains(&program.len()) {
+                    return Err(BitcoinError::ParseError(
+                        "invalid witness program length".to_string(),
+                    ));
+                }
+            }
+            _ => {
+                return Err(BitcoinError::ParseError(
+                    "witness version does not match bech32/bech32m variant".to_string(),
+                ))
+            }
+        }
+
+        let kind = if witness_version == 1 && program.len() == 32 {
+            AddressKind::Taproot
+        } else {
+            AddressKind::SegwitV0
+        };
+
+        let mut script_pubkey = Vec::with_capacity(program.len() + 2);
+        script_pubkey.push(witness_version_opcode(witness_version));
+        script_pubkey.push(program.len() as u8);
+        script_pubkey.extend_from_slice(&program);
+
+        Ok(Address {
+            network,
+            kind,
+            script_pubkey,
+        })
+    }
+}
+
+fn p2pkh_script(hash: &[u8]) -> Vec<u8> {
+    let mut script = Vec::with_capacity(25);
+    script.push(0x76); // OP_DUP
+    script.push(0xa9); // OP_HASH160
+    script.push(hash.len() as u8);
+    script.extend_from_slice(hash);
+    script.push(0x88); // OP_EQUALVERIFY
+    script.push(0xac); // OP_CHECKSIG
+    script
+}
+
+fn p2sh_script(hash: &[u8]) -> Vec<u8> {
+    let mut script = Vec::with_capacity(23);
+    script.push(0xa9); // OP_HASH160
+    script.push(hash.len() as u8);
+    script.extend_from_slice(hash);
+    script.push(0x87); // OP_EQUAL
+    script
+}
+
+fn witness_version_opcode(version: u8) -> u8 {
+    if version == 0 {
+        0x00 // OP_0
+    } else {
+        0x50 + version // OP_1 .. OP_16
+    }
+}
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+fn base58_decode(s: &str) -> Result<Vec<u8>, BitcoinError> {
+    let mut bytes: Vec<u8> = vec![0];
+    for c in s.chars() {
+        let digit = BASE58_ALPHABET
+            .iter()
+            .position(|&b| b == c as u8)
+            .ok_or_else(|| BitcoinError::ParseError(format!("invalid base58 character: {c}")))?
+            as u32;
+
+        let mut carry = digit;
+        for byte in bytes.iter_mut().rev() {
+            carry += (*byte as u32) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, (carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    let leading_zeros = s.chars().take_while(|&c| c == '1').count();
+    let first_nonzero = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    let mut decoded = vec![0u8; leading_zeros];
+    decoded.extend_from_slice(&bytes[first_nonzero..]);
+    Ok(decoded)
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut msg = data.to_vec();
+    let bit_len = (data.len() as u64) * 8;
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    msg.extend_from_slice(&bit_len.to_be_bytes());
+
+    for chunk in msg.chunks(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in w.iter_mut().take(16).enumerate() {
+            *word = u32::from_be_bytes(chunk[i * 4..i * 4 + 4].try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let (mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh) =
+            (h[0], h[1], h[2], h[3], h[4], h[5], h[6], h[7]);
+
+        for (i, k) in K.iter().enumerate() {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ ((!e) & g);
+            let temp1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        h[0] = h[0].wrapping_add(a);
+        h[1] = h[1].wrapping_add(b);
+        h[2] = h[2].wrapping_add(c);
+        h[3] = h[3].wrapping_add(d);
+        h[4] = h[4].wrapping_add(e);
+        h[5] = h[5].wrapping_add(f);
+        h[6] = h[6].wrapping_add(g);
+        h[7] = h[7].wrapping_add(hh);
+    }
+
+    let mut out = [0u8; 32];
+    for (i, word) in h.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+fn double_sha256(data: &[u8]) -> [u8; 32] {
+    sha256(&sha256(data))
+}
+
+const BECH32_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bech32Variant {
+    Bech32,
+    Bech32m,
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GEN: [u32; 5] = [
+        0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3,
+    ];
+    let mut chk: u32 = 1;
+    for &v in values {
+        let b = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ (v as u32);
+        for (i, gen) in GEN.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= gen;
+            }
+        }
+    }
+    chk
+}
+
+fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut v = Vec::with_capacity(hrp.len() * 2 + 1);
+    v.extend(hrp.bytes().map(|b| b >> 5));
+    v.push(0);
+    v.extend(hrp.bytes().map(|b| b & 31));
+    v
+}
+
+fn bech32_verify_checksum(hrp: &str, data: &[u8]) -> Option<Bech32Variant> {
+    let mut values = bech32_hrp_expand(hrp);
+    values.extend_from_slice(data);
+    match bech32_polymod(&values) {
+        1 => Some(Bech32Variant::Bech32),
+        0x2bc830a3 => Some(Bech32Variant::Bech32m),
+        _ => None,
+    }
+}
+
+fn bech32_decode(s: &str) -> Result<(String, Vec<u8>, Bech32Variant), BitcoinError> {
+    if s.chars().any(|c| c.is_ascii_uppercase()) && s.chars().any(|c| c.is_ascii_lowercase()) {
+        return Err(BitcoinError::ParseError(
+            "mixed-case bech32 string".to_string(),
+        ));
+    }
+    let lower = s.to_ascii_lowercase();
+    let pos = lower
+        .rfind('1')
+        .ok_or_else(|| BitcoinError::ParseError("missing bech32 separator".to_string()))?;
+    if pos == 0 || pos + 7 > lower.len() {
+        return Err(BitcoinError::ParseError(
+            "invalid bech32 separator position".to_string(),
+        ));
+    }
+    let hrp = &lower[..pos];
+    let data_part = &lower[pos + 1..];
+
+    let mut data = Vec::with_capacity(data_part.len());
+    for c in data_part.chars() {
+        let v = BECH32_CHARSET
+            .iter()
+            .position(|&b| b == c as u8)
+            .ok_or_else(|| BitcoinError::ParseError(format!("invalid bech32 character: {c}")))?
+            as u8;
+        data.push(v);
+    }
+
+    let variant = bech32_verify_checksum(hrp, &data)
+        .ok_or_else(|| BitcoinError::ParseError("invalid bech32 checksum".to_string()))?;
+
+    let payload = data[..data.len() - 6].to_vec();
+    Ok((hrp.to_string(), payload, variant))
+}
+
+fn convert_bits(data: &[u8], from_bits: u32, to_bits: u32, pad: bool) -> Result<Vec<u8>, BitcoinError> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut ret = Vec::new();
+    let max_v = (1u32 << to_bits) - 1;
+    for &value in data {
+        if (value as u32) >> from_bits != 0 {
+            return Err(BitcoinError::ParseError(
+                "invalid bech32 data value".to_string(),
+            ));
+        }
+        acc = (acc << from_bits) | value as u32;
+        bits += from_bits;
+        while bits >= to_bits {
+            bits -= to_bits;
+            ret.push(((acc >> bits) & max_v) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            ret.push(((acc << (to_bits - bits)) & max_v) as u8);
+        }
+    } else if bits >= from_bits || ((acc << (to_bits - bits)) & max_v) != 0 {
+        return Err(BitcoinError::ParseError(
+            "invalid bech32 padding".to_string(),
+        ));
+    }
+    Ok(ret)
+}
+
 // Simple CLI argument parser
-pub fn parse_cli_args(args: &[String]) -> Result<CliCommand, BitcoinError> {
+pub fn parse_cli_args(args: &[String], network: Network) -> Result<CliCommand, BitcoinError> {
     if args.is_empty() {
         return Err(BitcoinError::ParseError("No command provided".to_string()));
     }
@@ -150,9 +823,18 @@ pub fn parse_cli_args(args: &[String]) -> Result<CliCommand, BitcoinError> {
                 .parse::<u64>()
                 .map_err(|_| BitcoinError::InvalidAmount)?;
 
-            let address = args[2].clone();
+            let address = Address::parse(&args[2], network)?;
+
+            let fee = match args.get(3) {
+                Some(raw) => raw.parse::<u64>().map_err(|_| BitcoinError::InvalidAmount)?,
+                None => DEFAULT_FEE,
+            };
 
-            Ok(CliCommand::Send { amount, address })
+            Ok(CliCommand::Send {
+                amount,
+                address,
+                fee,
+            })
         }
         "balance" => {
             if args.len() > 1 {
@@ -167,11 +849,14 @@ pub fn parse_cli_args(args: &[String]) -> Result<CliCommand, BitcoinError> {
             cmd
         ))),
     }
-    // TODO: Match args to "send" or "balance" commands and parse required arguments
 }
 
 pub enum CliCommand {
-    Send { amount: u64, address: String },
+    Send {
+        amount: u64,
+        address: Address,
+        fee: u64,
+    },
     Balance,
 }
 
@@ -186,35 +871,67 @@ impl TryFrom<&[u8]> for LegacyTransaction {
 
         let mut offset = 0;
 
-        // Version (4 bytes)
-        let version = i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
-        offset += 4;
+        let version = read_i32(data, &mut offset)?;
+
+        // BIP-144: a marker byte of 0x00 means this is the SegWit wire format
+        // and must be immediately followed by the flag byte 0x01; any other
+        // flag value is rejected.
+        let mut segwit = false;
+        if data.get(offset) == Some(&0x00) {
+            let flag = *data
+                .get(offset + 1)
+                .ok_or(BitcoinError::InvalidTransaction)?;
+            if flag != 0x01 {
+                return Err(BitcoinError::InvalidTransaction);
+            }
+            segwit = true;
+            offset += 2;
+        }
 
-        // Input count and create dummy inputs
         let (input_count, size) = read_compact_size(data, offset)?;
         offset += size;
-        let inputs = (0..input_count)
-            .map(|_| TxInput {
-                previous_output: OutPoint {
-                    txid: [0; 32],
-                    vout: 0,
-                },
-                script_sig: vec![],
-                sequence: 0xffffffff,
-            })
-            .collect();
+        if segwit && input_count == 0 {
+            return Err(BitcoinError::InvalidTransaction);
+        }
+        let mut inputs = Vec::new();
+        for _ in 0..input_count {
+            let txid = read_bytes32(data, &mut offset)?;
+            let vout = read_u32(data, &mut offset)?;
+            let script_sig = read_compact_bytes(data, &mut offset)?;
+            let sequence = read_u32(data, &mut offset)?;
+            inputs.push(TxInput {
+                previous_output: OutPoint { txid, vout },
+                script_sig,
+                sequence,
+                witness: Vec::new(),
+            });
+        }
 
-        // Output count and create dummy outputs
         let (output_count, size) = read_compact_size(data, offset)?;
-        let outputs = (0..output_count)
-            .map(|_| TxOutput {
-                value: 0,
-                script_pubkey: vec![],
-            })
-            .collect();
+        offset += size;
+        let mut outputs = Vec::new();
+        for _ in 0..output_count {
+            let value = read_u64(data, &mut offset)?;
+            let script_pubkey = read_compact_bytes(data, &mut offset)?;
+            outputs.push(TxOutput {
+                value,
+                script_pubkey,
+            });
+        }
 
-        // Lock time (last 4 bytes)
-        let lock_time = u32::from_le_bytes(data[data.len() - 4..].try_into().unwrap());
+        if segwit {
+            for input in &mut inputs {
+                let (witness_count, size) = read_compact_size(data, offset)?;
+                offset += size;
+                let mut witness = Vec::new();
+                for _ in 0..witness_count {
+                    witness.push(read_compact_bytes(data, &mut offset)?);
+                }
+                input.witness = witness;
+            }
+        }
+
+        let lock_time = read_u32(data, &mut offset)?;
 
         Ok(LegacyTransaction {
             version,
@@ -223,38 +940,176 @@ impl TryFrom<&[u8]> for LegacyTransaction {
             lock_time,
         })
     }
+}
+
+// A Bitcoin consensus-encoded variable-length integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactSize(pub u64);
+
+impl CompactSize {
+    pub fn encode(&self) -> Vec<u8> {
+        match self.0 {
+            0..=252 => vec![self.0 as u8],
+            253..=0xffff => {
+                let mut out = vec![0xfd];
+                out.extend_from_slice(&(self.0 as u16).to_le_bytes());
+                out
+            }
+            0x1_0000..=0xffff_ffff => {
+                let mut out = vec![0xfe];
+                out.extend_from_slice(&(self.0 as u32).to_le_bytes());
+                out
+            }
+            _ => {
+                let mut out = vec![0xff];
+                out.extend_from_slice(&self.0.to_le_bytes());
+                out
+            }
+        }
+    }
 
-    // TODO: Parse binary data into a LegacyTransaction
-    // Minimum length is 10 bytes (4 version + 4 inputs count + 4 lock_time)
+    pub fn decode(data: &[u8], offset: usize) -> Result<(CompactSize, usize), BitcoinError> {
+        let first = *data
+            .get(offset)
+            .ok_or_else(|| BitcoinError::ParseError("truncated CompactSize".to_string()))?;
+        match first {
+            0..=252 => Ok((CompactSize(first as u64), 1)),
+            253 => {
+                let value = u16::from_le_bytes(
+                    data.get(offset + 1..offset + 3)
+                        .ok_or_else(|| BitcoinError::ParseError("truncated CompactSize".to_string()))?
+                        .try_into()
+                        .unwrap(),
+                ) as u64;
+                if value <= 252 {
+                    return Err(BitcoinError::ParseError(
+                        "non-minimal CompactSize encoding".to_string(),
+                    ));
+                }
+                Ok((CompactSize(value), 3))
+            }
+            254 => {
+                let value = u32::from_le_bytes(
+                    data.get(offset + 1..offset + 5)
+                        .ok_or_else(|| BitcoinError::ParseError("truncated CompactSize".to_string()))?
+                        .try_into()
+                        .unwrap(),
+                ) as u64;
+                if value <= 0xffff {
+                    return Err(BitcoinError::ParseError(
+                        "non-minimal CompactSize encoding".to_string(),
+                    ));
+                }
+                Ok((CompactSize(value), 5))
+            }
+            255 => {
+                let value = u64::from_le_bytes(
+                    data.get(offset + 1..offset + 9)
+                        .ok_or_else(|| BitcoinError::ParseError("truncated CompactSize".to_string()))?
+                        .try_into()
+                        .unwrap(),
+                );
+                if value <= 0xffff_ffff {
+                    return Err(BitcoinError::ParseError(
+                        "non-minimal CompactSize encoding".to_string(),
+                    ));
+                }
+                Ok((CompactSize(value), 9))
+            }
+        }
+    }
 }
 
 fn read_compact_size(data: &[u8], offset: usize) -> Result<(u64, usize), BitcoinError> {
-    let first = *data.get(offset).ok_or(BitcoinError::InvalidTransaction)?;
-    match first {
-        0..=252 => Ok((first as u64, 1)),
-        253 => Ok((
-            u16::from_le_bytes(data[offset + 1..offset + 3].try_into().unwrap()) as u64,
-            3,
-        )),
-        254 => Ok((
-            u32::from_le_bytes(data[offset + 1..offset + 5].try_into().unwrap()) as u64,
-            5,
-        )),
-        255 => Ok((
-            u64::from_le_bytes(data[offset + 1..offset + 9].try_into().unwrap()),
-            9,
-        )),
-    }
+    let (value, size) =
+        CompactSize::decode(data, offset).map_err(|_| BitcoinError::InvalidTransaction)?;
+    Ok((value.0, size))
+}
+
+fn read_i32(data: &[u8], offset: &mut usize) -> Result<i32, BitcoinError> {
+    let bytes = data
+        .get(*offset..*offset + 4)
+        .ok_or(BitcoinError::InvalidTransaction)?;
+    *offset += 4;
+    Ok(i32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u32(data: &[u8], offset: &mut usize) -> Result<u32, BitcoinError> {
+    let bytes = data
+        .get(*offset..*offset + 4)
+        .ok_or(BitcoinError::InvalidTransaction)?;
+    *offset += 4;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(data: &[u8], offset: &mut usize) -> Result<u64, BitcoinError> {
+    let bytes = data
+        .get(*offset..*offset + 8)
+        .ok_or(BitcoinError::InvalidTransaction)?;
+    *offset += 8;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_bytes32(data: &[u8], offset: &mut usize) -> Result<[u8; 32], BitcoinError> {
+    let bytes = data
+        .get(*offset..*offset + 32)
+        .ok_or(BitcoinError::InvalidTransaction)?;
+    *offset += 32;
+    Ok(bytes.try_into().unwrap())
+}
+
+fn read_compact_bytes(data: &[u8], offset: &mut usize) -> Result<Vec<u8>, BitcoinError> {
+    let (len, size) = read_compact_size(data, *offset)?;
+    *offset += size;
+    let len = len as usize;
+    let end = offset
+        .checked_add(len)
+        .ok_or(BitcoinError::InvalidTransaction)?;
+    let bytes = data.get(*offset..end).ok_or(BitcoinError::InvalidTransaction)?;
+    *offset = end;
+    Ok(bytes.to_vec())
 }
 
 // Custom serialization for transaction
 impl BitcoinSerialize for LegacyTransaction {
     fn serialize(&self) -> Vec<u8> {
+        let has_witness = self.inputs.iter().any(|input| !input.witness.is_empty());
+
         let mut result = Vec::new();
         result.extend_from_slice(&self.version.to_le_bytes());
-        result.extend_from_slice(&self.lock_time.to_le_bytes());
 
+        if has_witness {
+            result.push(0x00); // marker
+            result.push(0x01); // flag
+        }
+
+        result.extend_from_slice(&CompactSize(self.inputs.len() as u64).encode());
+        for input in &self.inputs {
+            result.extend_from_slice(&input.previous_output.txid);
+            result.extend_from_slice(&input.previous_output.vout.to_le_bytes());
+            result.extend_from_slice(&CompactSize(input.script_sig.len() as u64).encode());
+            result.extend_from_slice(&input.script_sig);
+            result.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+
+        result.extend_from_slice(&CompactSize(self.outputs.len() as u64).encode());
+        for output in &self.outputs {
+            result.extend_from_slice(&output.value.to_le_bytes());
+            result.extend_from_slice(&CompactSize(output.script_pubkey.len() as u64).encode());
+            result.extend_from_slice(&output.script_pubkey);
+        }
+
+        if has_witness {
+            for input in &self.inputs {
+                result.extend_from_slice(&CompactSize(input.witness.len() as u64).encode());
+                for item in &input.witness {
+                    result.extend_from_slice(&CompactSize(item.len() as u64).encode());
+                    result.extend_from_slice(item);
+                }
+            }
+        }
+
+        result.extend_from_slice(&self.lock_time.to_le_bytes());
         result
-        // TODO: Serialize only version and lock_time (simplified)
     }
 }